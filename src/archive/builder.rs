@@ -0,0 +1,94 @@
+use anyhow::anyhow;
+use std::fs::File;
+use std::io::{Write, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::collections::VecDeque;
+use crate::archive::{Archive, serialize_header, DEFAULT_HEADER_NAME, DEFAULT_ARCHIVE_NAME, DEFAULT_ROOT_NAME};
+use crate::archive::file::{SFile, SFolder};
+use crate::archive::checksum::native_checksum;
+
+/// Builds a new Shaiya archive by inserting files and folders into a virtual filesystem, then
+/// flushing a complete SAH/SAF pair to disk.
+///
+/// This is the exact inverse of [`Archive::parse`] - callers add entries by virtual path, and
+/// [`Builder::finish`] writes the resulting tree out in the layout the client expects.
+pub struct Builder {
+    header_path: PathBuf,
+    data_path: PathBuf,
+    data_file: File,
+    root: SFolder,
+    offset: u64,
+}
+
+impl Builder {
+
+    /// Creates a new builder, writing the archive into the given directory.
+    ///
+    /// # Arguments
+    /// * `path`    - The directory to write the header and data files into.
+    pub fn new(path: &Path) -> anyhow::Result<Self> {
+        let header_path = path.join(Path::new(DEFAULT_HEADER_NAME));
+        let data_path = path.join(Path::new(DEFAULT_ARCHIVE_NAME));
+
+        // If the files already exist, we should return an error - we don't want to overwrite everything.
+        if header_path.exists() {
+            return Err(anyhow!("Header file already exists."));
+        } else if data_path.exists() {
+            return Err(anyhow!("Data file already exists."));
+        }
+
+        Ok(Self {
+            data_file: File::create(&data_path)?,
+            header_path,
+            data_path,
+            root: SFolder::new(DEFAULT_ROOT_NAME.to_owned()),
+            offset: 0,
+        })
+    }
+
+    /// Adds a file's data to the archive at the given virtual path, creating any missing parent
+    /// folders.
+    ///
+    /// # Arguments
+    /// * `path`    - The virtual path of the file (e.g. `"character/skill.sdata"`).
+    /// * `data`    - The raw bytes of the file.
+    pub fn add_file(&mut self, path: &str, data: &[u8]) -> anyhow::Result<()> {
+        self.data_file.write_all(data)?;
+
+        let name = path.rsplit('/').next().unwrap_or(path).to_owned();
+        let file = SFile {
+            name,
+            offset: self.offset,
+            length: data.len() as u64,
+            checksum: native_checksum(data),
+        };
+
+        self.offset += data.len() as u64;
+
+        let mut parts: VecDeque<&str> = path.split('/').collect();
+        self.root.insert(&mut parts, file);
+        Ok(())
+    }
+
+    /// Adds an empty folder at the given virtual path, creating any missing parent folders.
+    ///
+    /// # Arguments
+    /// * `path`    - The virtual path of the folder.
+    pub fn add_dir(&mut self, path: &str) {
+        let mut parts: VecDeque<&str> = path.split('/').collect();
+        self.root.insert_dir(&mut parts);
+    }
+
+    /// Flushes the header and data files to disk, and re-opens the result as a queryable
+    /// [`Archive`].
+    pub fn finish(mut self) -> anyhow::Result<Archive> {
+        self.data_file.flush()?;
+        self.data_file.seek(SeekFrom::Start(0))?;
+
+        let mut header_file = File::create(&self.header_path)?;
+        serialize_header(&self.root, &mut header_file)?;
+        header_file.flush()?;
+
+        Archive::open(&self.header_path, &self.data_path)
+    }
+}
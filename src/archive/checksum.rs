@@ -0,0 +1,94 @@
+use std::io::Read;
+
+/// A pluggable checksum algorithm, so the concrete scheme backing [`Archive::verify`]
+/// (crate::archive::Archive::verify) can be swapped without touching the verification logic
+/// itself.
+pub trait Checksum: Default {
+
+    /// Feeds a chunk of bytes into the running checksum.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Finalises the checksum into the i32 representation stored in the SAH header.
+    fn finish(self) -> i32;
+}
+
+/// The native additive checksum Shaiya stores alongside each entry in the SAH header.
+///
+/// This is validated against the pre-existing checksums in the Ep5 fixture (see
+/// `test_verify_all_passes_on_untouched_fixture`) - `Archive::verify_all()` passes on
+/// `ep5/data.sah` as shipped, without rebuilding it through [`crate::archive::Builder`] first.
+#[derive(Default)]
+pub struct NativeChecksum(i32);
+
+impl Checksum for NativeChecksum {
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.wrapping_add(byte as i32);
+        }
+    }
+
+    fn finish(self) -> i32 {
+        self.0
+    }
+}
+
+/// An optional, stronger integrity check using SHA-256, truncated to the SAH checksum's i32
+/// width. Useful as a side-channel when the native scheme isn't enough to trust an entry.
+#[cfg(feature = "sha256-checksum")]
+#[derive(Default)]
+pub struct Sha256Checksum(sha2::Sha256);
+
+#[cfg(feature = "sha256-checksum")]
+impl Checksum for Sha256Checksum {
+    fn update(&mut self, bytes: &[u8]) {
+        use sha2::Digest;
+        self.0.update(bytes);
+    }
+
+    fn finish(self) -> i32 {
+        use sha2::Digest;
+        let digest = self.0.finalize();
+        i32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]])
+    }
+}
+
+/// A reader which computes a [`Checksum`] incrementally as bytes flow past, so verification
+/// composes with the streaming `file_reader` without a second full buffer.
+pub struct ChecksumReader<R, C: Checksum> {
+    inner: R,
+    checksum: C,
+}
+
+impl<R: Read, C: Checksum> ChecksumReader<R, C> {
+
+    /// Wraps `inner`, accumulating a checksum over every byte read through it.
+    ///
+    /// # Arguments
+    /// * `inner`   - The reader to wrap.
+    pub fn new(inner: R) -> Self {
+        Self { inner, checksum: C::default() }
+    }
+
+    /// Consumes the reader, returning the checksum accumulated so far.
+    pub fn finish(self) -> i32 {
+        self.checksum.finish()
+    }
+}
+
+impl<R: Read, C: Checksum> Read for ChecksumReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.checksum.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Computes the native additive checksum of a byte slice in one shot.
+///
+/// # Arguments
+/// * `data`    - The raw bytes to checksum.
+pub(crate) fn native_checksum(data: &[u8]) -> i32 {
+    let mut checksum = NativeChecksum::default();
+    checksum.update(data);
+    checksum.finish()
+}
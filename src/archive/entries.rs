@@ -0,0 +1,19 @@
+use crate::archive::file::SFile;
+
+/// A single entry encountered while performing a depth-first walk of an archive's virtual
+/// filesystem, carrying the reconstructed slash-joined path alongside its file metadata.
+pub struct Entry {
+    pub path: String,
+    pub file: SFile,
+}
+
+/// An iterator over every file in an archive, produced by [`Archive::entries`](crate::archive::Archive::entries).
+pub struct Entries(pub(crate) std::vec::IntoIter<Entry>);
+
+impl Iterator for Entries {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Entry> {
+        self.0.next()
+    }
+}
@@ -1,6 +1,7 @@
-use std::io::Read;
-use byteorder::{ReadBytesExt, LittleEndian};
-use crate::utils::ShaiyaIo;
+use std::io::{Read, Write};
+use byteorder::{ReadBytesExt, WriteBytesExt, LittleEndian};
+use crate::utils::{ShaiyaIo, ShaiyaIoWrite};
+use crate::archive::entries::Entry;
 use std::collections::VecDeque;
 
 /// A virtual folder in a Shaiya archive.
@@ -16,7 +17,15 @@ pub struct SFile {
     pub name: String,
     pub offset: u64,
     pub length: u64,
-    checksum: i32,
+    pub(crate) checksum: i32,
+}
+
+impl SFile {
+
+    /// Gets the checksum recorded for this file in the SAH header.
+    pub fn checksum(&self) -> i32 {
+        self.checksum
+    }
 }
 
 impl SFolder {
@@ -75,6 +84,111 @@ impl SFolder {
         Ok(())
     }
 
+    /// Writes this folder's contents to a writable sink, in the exact layout that [`SFolder::parse`]
+    /// expects to read back.
+    ///
+    /// # Arguments
+    /// * `buf` - The writable sink.
+    pub(crate) fn write<T: Write>(&self, buf: &mut T) -> anyhow::Result<()> {
+        // Write the files.
+        buf.write_u32::<LittleEndian>(self.files.len() as u32)?;
+        for file in &self.files {
+            buf.write_length_prefixed_string(&file.name)?;
+            buf.write_u64::<LittleEndian>(file.offset)?;
+            buf.write_u32::<LittleEndian>(file.length as u32)?;
+            buf.write_i32::<LittleEndian>(file.checksum)?;
+        }
+
+        // Write the sub-directories.
+        buf.write_u32::<LittleEndian>(self.folders.len() as u32)?;
+        for folder in &self.folders {
+            buf.write_length_prefixed_string(&folder.name)?;
+            folder.write(buf)?;
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a file at the given path, creating any missing parent folders along the way.
+    ///
+    /// # Arguments
+    /// * `parts`   - The remaining components of the virtual path.
+    /// * `file`    - The file entry to insert.
+    pub(crate) fn insert(&mut self, parts: &mut VecDeque<&str>, file: SFile) {
+        if parts.len() <= 1 {
+            self.files.push(file);
+            return;
+        }
+
+        let name = parts.pop_front().unwrap();
+        match self.folders.iter_mut().find(|folder| folder.name.eq_ignore_ascii_case(name)) {
+            Some(folder) => folder.insert(parts, file),
+            None => {
+                let mut folder = SFolder::new(name.to_owned());
+                folder.insert(parts, file);
+                self.folders.push(folder);
+            }
+        }
+    }
+
+    /// Inserts a file at the given path, replacing any existing file at that path (matched
+    /// case-insensitively), and creating any missing parent folders along the way.
+    ///
+    /// # Arguments
+    /// * `parts`   - The remaining components of the virtual path.
+    /// * `file`    - The file entry to insert or replace.
+    pub(crate) fn upsert(&mut self, parts: &mut VecDeque<&str>, file: SFile) {
+        if parts.len() <= 1 {
+            match self.files.iter_mut().find(|existing| existing.name.eq_ignore_ascii_case(&file.name)) {
+                Some(existing) => *existing = file,
+                None => self.files.push(file),
+            }
+            return;
+        }
+
+        let name = parts.pop_front().unwrap();
+        match self.folders.iter_mut().find(|folder| folder.name.eq_ignore_ascii_case(name)) {
+            Some(folder) => folder.upsert(parts, file),
+            None => {
+                let mut folder = SFolder::new(name.to_owned());
+                folder.upsert(parts, file);
+                self.folders.push(folder);
+            }
+        }
+    }
+
+    /// Inserts an empty folder at the given path, creating any missing parent folders along the way.
+    ///
+    /// # Arguments
+    /// * `parts`   - The remaining components of the virtual path.
+    pub(crate) fn insert_dir(&mut self, parts: &mut VecDeque<&str>) {
+        let name = match parts.pop_front() {
+            Some(name) => name,
+            None => return,
+        };
+
+        match self.folders.iter_mut().find(|folder| folder.name.eq_ignore_ascii_case(name)) {
+            Some(folder) => folder.insert_dir(parts),
+            None => {
+                let mut folder = SFolder::new(name.to_owned());
+                folder.insert_dir(parts);
+                self.folders.push(folder);
+            }
+        }
+    }
+
+    /// Gets the name of this folder.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Counts the total number of files in this folder and all of its subdirectories.
+    pub(crate) fn file_count(&self) -> u32 {
+        self.files.len() as u32 + self.folders.iter()
+            .map(|folder| folder.file_count())
+            .sum::<u32>()
+    }
+
     /// Gets the subdirectories in this folder.
     pub fn subdirectories(&self) -> &Vec<SFolder> {
         &self.folders
@@ -85,9 +199,27 @@ impl SFolder {
         &self.files
     }
 
+    /// Recursively walks this folder, appending an [`Entry`] for every file found, with paths
+    /// joined onto `prefix`.
+    ///
+    /// # Arguments
+    /// * `prefix`  - The slash-joined path of this folder, relative to the root.
+    /// * `entries` - The vector to append discovered entries to.
+    pub(crate) fn walk(&self, prefix: &str, entries: &mut Vec<Entry>) {
+        for file in &self.files {
+            let path = join_path(prefix, &file.name);
+            entries.push(Entry { path, file: file.clone() });
+        }
+
+        for folder in &self.folders {
+            let path = join_path(prefix, &folder.name);
+            folder.walk(&path, entries);
+        }
+    }
+
     pub fn get(&self, parts: &mut VecDeque<&str>) -> Option<SFile> {
         // Loop through the parts of the path.
-        for part in parts.into_iter() {
+        for part in parts.iter_mut() {
             // Look for the file in the local files.
             for file in &self.files {
                 if file.name.eq_ignore_ascii_case(part) {
@@ -106,4 +238,13 @@ impl SFolder {
 
         None
     }
+}
+
+/// Joins a path component onto a slash-joined prefix, omitting the leading slash at the root.
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,80 @@
+use std::io::{Read, Seek, SeekFrom};
+
+/// A candidate magic value for a wrapped Shaiya `SData` container's leading header (e.g.
+/// `item/item.sdata`, `character/skill.sdata`), per the leading magic/version field the format is
+/// believed to carry.
+///
+/// **This has not been validated against real `.sdata` bytes** - there is no confirmed
+/// documentation of Shaiya's actual `SData` layout, and this crate has no fixture containing a
+/// genuinely wrapped entry to check it against. In practice every real entry seen so far has
+/// missed this magic and fallen through to the raw-passthrough path below, so until it's
+/// confirmed against real game data, treat [`SDataLayer`] as a strip-if-recognised, pass-through
+/// otherwise shim rather than a working decoder.
+const SDATA_MAGIC: &[u8; 4] = b"SDAT";
+
+/// The length of the leading magic (4 bytes) + version (u32) header.
+const HEADER_LEN: u64 = 8;
+
+/// The length of the trailing checksum block.
+const TRAILER_LEN: u64 = 4;
+
+/// A decode layer which strips a Shaiya `SData` container's leading magic/version header and
+/// trailing checksum block, exposing the inner payload as a normal `Read`.
+///
+/// Layers wrap an inner reader and can be composed on top of the streaming `file_reader`, so
+/// decoding never requires a full in-memory copy.
+///
+/// Scope note: this only strips a header/trailer envelope - it does **not** decrypt or
+/// decompress the payload. Without a real `.sdata` sample to reverse-engineer, there's no way to
+/// confirm whether Shaiya's container even uses compression, let alone which scheme. Wiring in a
+/// decompressor is future work once [`SDATA_MAGIC`] and the header layout are validated against
+/// real bytes. Until then, if the wrapped data doesn't carry the recognised header, `detect` falls
+/// back to passing `total_len` bytes straight through unchanged - which is the common case today.
+/// This whole layer is gated behind the `sdata-layer-unverified` feature for that reason.
+pub struct SDataLayer<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: Read + Seek> SDataLayer<R> {
+
+    /// Wraps `inner`, auto-detecting whether it holds a wrapped `SData` container.
+    ///
+    /// If the leading magic value is present, the header is stripped and the trailing checksum
+    /// block is excluded from future reads. Otherwise, `inner` is rewound and all `total_len`
+    /// bytes are passed through unchanged. See the type-level docs for why this is a best-effort
+    /// heuristic rather than a validated decoder.
+    ///
+    /// # Arguments
+    /// * `inner`       - The reader to wrap, positioned at the start of the entry.
+    /// * `total_len`   - The total length of the entry.
+    pub fn detect(mut inner: R, total_len: u64) -> anyhow::Result<Self> {
+        if total_len >= HEADER_LEN + TRAILER_LEN {
+            let mut header = [0u8; HEADER_LEN as usize];
+            inner.read_exact(&mut header)?;
+
+            if &header[..4] == SDATA_MAGIC {
+                let payload_len = total_len - HEADER_LEN - TRAILER_LEN;
+                return Ok(Self { inner, remaining: payload_len });
+            }
+
+            // Not a recognised container - rewind so the caller sees the raw bytes from the start.
+            inner.seek(SeekFrom::Start(0))?;
+        }
+
+        Ok(Self { inner, remaining: total_len })
+    }
+}
+
+impl<R: Read> Read for SDataLayer<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 {
+            return Ok(0);
+        }
+
+        let limit = self.remaining.min(buf.len() as u64) as usize;
+        let read = self.inner.read(&mut buf[..limit])?;
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// A single entry in an archive's manifest: a virtual path mapped to its offset, length, and
+/// checksum in the backing `.saf` file.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ListingEntry {
+    pub path: String,
+    pub offset: u64,
+    pub length: u64,
+    pub checksum: i32,
+}
+
+/// A serialisable manifest of an archive's whole virtual filesystem - every file's offset and
+/// size into the backing blob, so external tooling can index or diff archives, or stream-fetch a
+/// single file by offset without reparsing the SAH.
+///
+/// Pairs with [`Archive::from_listing`](crate::archive::Archive::from_listing) to reconstruct a
+/// queryable archive from the manifest and a `.saf` file alone.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Listing {
+    pub root_name: String,
+    pub entries: Vec<ListingEntry>,
+}
+
+impl Listing {
+
+    /// Serialises this listing to a JSON string.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses a listing from a JSON string.
+    ///
+    /// # Arguments
+    /// * `json`    - The JSON manifest to parse.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
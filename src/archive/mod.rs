@@ -2,24 +2,75 @@ use anyhow::anyhow;
 use std::fs::{File, OpenOptions};
 use std::path::Path;
 use file::{SFolder, SFile};
-use std::io::{Cursor, Read, Seek, SeekFrom};
-use crate::utils::ShaiyaIo;
-use byteorder::{ReadBytesExt, LittleEndian};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use crate::utils::{ShaiyaIo, ShaiyaIoWrite};
+use byteorder::{ReadBytesExt, WriteBytesExt, LittleEndian};
 use std::collections::VecDeque;
 
 mod file;
+mod builder;
+mod reader;
+mod entries;
+mod checksum;
+#[cfg(feature = "sdata-layer-unverified")]
+mod layers;
+mod listing;
+
+pub use builder::Builder;
+pub use reader::FileReader;
+pub use entries::{Entry, Entries};
+pub use checksum::{Checksum, NativeChecksum, ChecksumReader};
+#[cfg(feature = "sdata-layer-unverified")]
+pub use layers::SDataLayer;
+pub use listing::{Listing, ListingEntry};
 
 /// The magic value of the SAH file.
-pub const SAH_MAGIC_VALUE: &'static str = "SAH";
+pub const SAH_MAGIC_VALUE: &str = "SAH";
 
 /// The default name of the Shaiya archive header file.
-pub const DEFAULT_HEADER_NAME: &'static str = "data.sah";
+pub const DEFAULT_HEADER_NAME: &str = "data.sah";
 
 /// The default name of the Shaiya archive data file.
-pub const DEFAULT_ARCHIVE_NAME: &'static str = "data.saf";
+pub const DEFAULT_ARCHIVE_NAME: &str = "data.saf";
 
 /// The default name of the root data folder.
-pub const DEFAULT_ROOT_NAME: &'static str = "data";
+pub const DEFAULT_ROOT_NAME: &str = "data";
+
+/// Serialises a virtual filesystem out to a complete SAH header, in the exact layout
+/// [`Archive::parse`] expects to read back. Shared by [`Builder::finish`] and
+/// [`Archive::write_header`].
+///
+/// # Arguments
+/// * `root`    - The root folder to serialise.
+/// * `out`     - The writable sink.
+pub(crate) fn serialize_header<W: Write>(root: &SFolder, out: &mut W) -> anyhow::Result<()> {
+    out.write_all(SAH_MAGIC_VALUE.as_bytes())?;
+    out.write_u32::<LittleEndian>(0)?; // Reserved.
+    out.write_u32::<LittleEndian>(root.file_count())?;
+    out.write_all(&[0u8; 40])?; // Reserved.
+    out.write_length_prefixed_string(root.name())?;
+    root.write(out)?;
+    Ok(())
+}
+
+/// Validates an entry's virtual path is safe to join onto an extraction directory, rejecting
+/// anything that could escape it (a `..` component, an absolute path, or a path prefix) - see
+/// [`Archive::unpack`].
+///
+/// # Arguments
+/// * `path`    - The entry's slash-joined virtual path, as reconstructed by [`Archive::entries`].
+fn sanitize_entry_path(path: &str) -> anyhow::Result<&Path> {
+    let path = Path::new(path);
+
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(_) => {}
+            other => return Err(anyhow!("Unsafe entry path '{}': rejected component {:?}", path.display(), other)),
+        }
+    }
+
+    Ok(path)
+}
 
 /// An `archive` is a binary format which contains a header ("SAH"), and a data file ("SAF").
 ///
@@ -29,7 +80,7 @@ pub const DEFAULT_ROOT_NAME: &'static str = "data";
 /// The `data file` is just a contiguous block of data, containing the data of every file in the archive.
 /// This allow for random access to files.
 pub struct Archive {
-    header_file: File,
+    header_file: Option<File>,
     data_file: File,
     pub root: SFolder,
 }
@@ -54,7 +105,7 @@ impl Archive {
         }
 
         Ok(Self {
-            header_file:    File::create(header_file_path)?,
+            header_file:    Some(File::create(header_file_path)?),
             data_file:      File::create(data_file_path)?,
             root:           SFolder::new(DEFAULT_ROOT_NAME.to_owned()),
         })
@@ -76,16 +127,47 @@ impl Archive {
             .open(data_path)?;
         let root = SFolder::new(DEFAULT_ROOT_NAME.to_owned());
 
-        let mut archive = Self { header_file, data_file, root };
+        let mut archive = Self { header_file: Some(header_file), data_file, root };
         archive.parse()?;
         Ok(archive)
     }
 
+    /// Reconstructs a queryable archive from a [`Listing`] and a backing `.saf` file, without
+    /// re-reading or even requiring a `.sah` header.
+    ///
+    /// # Arguments
+    /// * `listing`     - The manifest to rebuild the virtual filesystem from.
+    /// * `data_path`   - The path to the `.saf` data file the listing's offsets refer to.
+    pub fn from_listing(listing: &Listing, data_path: &Path) -> anyhow::Result<Self> {
+        let data_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(data_path)?;
+
+        let mut root = SFolder::new(listing.root_name.clone());
+        for entry in &listing.entries {
+            let name = entry.path.rsplit('/').next().unwrap_or(&entry.path).to_owned();
+            let file = SFile {
+                name,
+                offset: entry.offset,
+                length: entry.length,
+                checksum: entry.checksum,
+            };
+
+            let mut parts: VecDeque<&str> = entry.path.split('/').collect();
+            root.insert(&mut parts, file);
+        }
+
+        Ok(Self { header_file: None, data_file, root })
+    }
+
     /// Parses the archive files, and populates the virtual filesystem.
     pub fn parse(&mut self) -> anyhow::Result<()> {
         // Read the contents of the header file.
+        let header_file = self.header_file.as_mut()
+            .ok_or_else(|| anyhow!("Archive has no backing header file to parse."))?;
         let mut header_data = Vec::new();
-        self.header_file.read_to_end(&mut header_data)?;
+        header_file.read_to_end(&mut header_data)?;
 
         // Create a cursor to read from.
         let mut cursor = Cursor::new(header_data);
@@ -109,6 +191,15 @@ impl Archive {
         self.root.parse(&mut cursor)
     }
 
+    /// Gets a bounded, seekable reader over a file's data, without loading it into memory.
+    ///
+    /// # Arguments
+    /// * `file`    - The file to get a reader for.
+    pub fn file_reader(&self, file: &SFile) -> anyhow::Result<FileReader> {
+        let handle = self.data_file.try_clone()?;
+        FileReader::new(handle, file.offset, file.length)
+    }
+
     /// Gets the data for a specified file.
     ///
     /// # Arguments
@@ -116,11 +207,10 @@ impl Archive {
     pub fn file_data(&mut self, file: &SFile) -> anyhow::Result<Vec<u8>> {
         // Create a vector to store the data.
         let mut data: Vec<u8> = vec![0; file.length as usize];
-        let slice = data.as_mut_slice();
 
-        // Seek to a position in the data file and read the file's data.
-        self.data_file.seek(SeekFrom::Start(file.offset))?;
-        self.data_file.read(slice)?;
+        // Stream the file's data through a bounded reader, clamped to its region of the data file.
+        let mut reader = self.file_reader(file)?;
+        reader.read_exact(&mut data)?;
         Ok(data)
     }
 
@@ -133,11 +223,189 @@ impl Archive {
         let mut parts: VecDeque<&str> = path.split("/").collect();
         self.root.get(&mut parts)
     }
+
+    /// Performs a depth-first walk of the virtual filesystem, yielding every file alongside its
+    /// reconstructed slash-joined path.
+    pub fn entries(&self) -> Entries {
+        let mut entries = Vec::new();
+        self.root.walk("", &mut entries);
+        Entries(entries.into_iter())
+    }
+
+    /// Extracts the virtual filesystem to `dest`, recreating its folder structure and streaming
+    /// each file's data through [`Archive::file_reader`] rather than buffering it fully. Folders
+    /// are created only as parents of extracted files; folders that hold no files are not created.
+    ///
+    /// Entry paths come from the header, which may be attacker-controlled or corrupted - each one
+    /// is sanitized before being joined onto `dest`, so no entry can escape the destination
+    /// directory via `..`, an absolute path, or a path prefix.
+    ///
+    /// # Arguments
+    /// * `dest`    - The directory to extract into.
+    /// * `prefix`  - If given, only entries within this subtree (e.g. `"character"`) are extracted.
+    pub fn unpack(&mut self, dest: &Path, prefix: Option<&str>) -> anyhow::Result<()> {
+        let prefix = prefix.map(|prefix| prefix.to_ascii_lowercase());
+
+        for entry in self.entries() {
+            if let Some(prefix) = &prefix {
+                let path_lower = entry.path.to_ascii_lowercase();
+                if &path_lower != prefix && !path_lower.starts_with(&format!("{}/", prefix)) {
+                    continue;
+                }
+            }
+
+            let out_path = dest.join(sanitize_entry_path(&entry.path)?);
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            let mut reader = self.file_reader(&entry.file)?;
+            let mut out_file = File::create(out_path)?;
+            std::io::copy(&mut reader, &mut out_file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-reads a file's bytes and confirms they match its stored checksum, without buffering
+    /// the whole file - the checksum is computed incrementally as bytes stream past.
+    ///
+    /// # Arguments
+    /// * `file`    - The file to verify.
+    pub fn verify(&self, file: &SFile) -> anyhow::Result<()> {
+        let reader = self.file_reader(file)?;
+        let mut checked = ChecksumReader::<_, NativeChecksum>::new(reader);
+        std::io::copy(&mut checked, &mut std::io::sink())?;
+
+        let computed = checked.finish();
+        if computed != file.checksum() {
+            return Err(anyhow!(
+                "Checksum mismatch for '{}': expected {}, got {}", file.name, file.checksum(), computed
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies every file in the archive. See [`Archive::verify`].
+    pub fn verify_all(&self) -> anyhow::Result<()> {
+        for entry in self.entries() {
+            self.verify(&entry.file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets a file's data, stripping a wrapped `SData` container's header/trailer if one is
+    /// recognised.
+    ///
+    /// Many archived entries (e.g. `item/item.sdata`, `character/skill.sdata`) are believed to be
+    /// stored in Shaiya's wrapped `SData` container rather than as plain bytes - this detects and
+    /// strips it through [`SDataLayer`], while [`Archive::file_data`] still returns the raw bytes.
+    ///
+    /// Gated behind the `sdata-layer-unverified` feature: see [`SDataLayer`]'s docs. The detection
+    /// is an unvalidated heuristic and no decompression or decryption is implemented, so for
+    /// entries that don't match the recognised header this is currently equivalent to
+    /// `file_data`. Don't rely on this for anything beyond experimentation until the real format
+    /// is confirmed.
+    ///
+    /// # Arguments
+    /// * `file`    - The file to decode.
+    #[cfg(feature = "sdata-layer-unverified")]
+    pub fn decoded_data(&self, file: &SFile) -> anyhow::Result<Vec<u8>> {
+        let reader = self.file_reader(file)?;
+        let mut layer = SDataLayer::detect(reader, file.length)?;
+
+        let mut data = Vec::new();
+        layer.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Merges a patch archive's entries onto this one: an entry is added if its path doesn't
+    /// already exist, or replaces the existing entry of the same case-insensitive path.
+    ///
+    /// Patch bytes are streamed straight from the patch's `.saf` into the end of this archive's
+    /// `.saf`, with offsets rewritten to match, rather than buffering each entry fully in memory.
+    ///
+    /// # Arguments
+    /// * `patch`   - The patch archive to merge in.
+    pub fn overlay(&mut self, patch: &mut Archive) -> anyhow::Result<()> {
+        for entry in patch.entries() {
+            let reader = patch.file_reader(&entry.file)?;
+            self.put(&entry.path, reader)?;
+        }
+
+        self.write_header()
+    }
+
+    /// Applies a sequence of patch archives in order, so a base archive plus N sequential
+    /// updates can be flattened into a single coherent archive. See [`Archive::overlay`].
+    ///
+    /// # Arguments
+    /// * `patches` - The patch archives to apply, in order.
+    pub fn apply_patches(&mut self, patches: &mut [Archive]) -> anyhow::Result<()> {
+        for patch in patches.iter_mut() {
+            self.overlay(patch)?;
+        }
+
+        Ok(())
+    }
+
+    /// Streams a file's data to the end of the data file and inserts/replaces its entry at the
+    /// given virtual path, computing its checksum incrementally as bytes flow past.
+    ///
+    /// # Arguments
+    /// * `path`    - The virtual path to insert or replace.
+    /// * `data`    - A reader over the file's raw bytes.
+    fn put<R: Read>(&mut self, path: &str, data: R) -> anyhow::Result<()> {
+        let offset = self.data_file.seek(SeekFrom::End(0))?;
+
+        let mut checksummed = ChecksumReader::<_, NativeChecksum>::new(data);
+        let length = std::io::copy(&mut checksummed, &mut self.data_file)?;
+        let checksum = checksummed.finish();
+
+        let name = path.rsplit('/').next().unwrap_or(path).to_owned();
+        let file = SFile { name, offset, length, checksum };
+
+        let mut parts: VecDeque<&str> = path.split('/').collect();
+        self.root.upsert(&mut parts, file);
+        Ok(())
+    }
+
+    /// Rewrites the `.sah` header file to reflect the current state of the virtual filesystem.
+    fn write_header(&mut self) -> anyhow::Result<()> {
+        let header_file = self.header_file.as_mut()
+            .ok_or_else(|| anyhow!("Archive has no backing header file to write."))?;
+
+        header_file.seek(SeekFrom::Start(0))?;
+        header_file.set_len(0)?;
+        serialize_header(&self.root, header_file)?;
+        header_file.flush()?;
+        Ok(())
+    }
+
+    /// Produces a serialisable manifest of the whole virtual filesystem - every path mapped to
+    /// its offset, length, and checksum in the backing `.saf` file.
+    pub fn listing(&self) -> Listing {
+        let entries = self.entries()
+            .map(|entry| ListingEntry {
+                path: entry.path,
+                offset: entry.file.offset,
+                length: entry.file.length,
+                checksum: entry.file.checksum(),
+            })
+            .collect();
+
+        Listing {
+            root_name: self.root.name().to_owned(),
+            entries,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::archive::Archive;
+    use crate::archive::{Archive, Builder};
     use std::path::Path;
 
     /// Tests the validity of a known-good Ep5 archive.
@@ -164,4 +432,348 @@ mod tests {
 
         Ok(())
     }
+
+    /// Tests that `file_reader` yields the same bytes as `file_data`, and stays within bounds.
+    #[test]
+    fn test_file_reader_is_bounded() -> anyhow::Result<()> {
+        use std::io::Read;
+
+        let mut archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        let file = archive.get("character/skill.sdata").expect("fixture should contain skill.sdata");
+
+        let expected = archive.file_data(&file)?;
+
+        let mut reader = archive.file_reader(&file)?;
+        let mut actual = Vec::new();
+        reader.read_to_end(&mut actual)?;
+        assert_eq!(expected, actual);
+
+        // Reading past the end of the entry should yield nothing further, not the next file's data.
+        let mut trailing = [0u8; 16];
+        assert_eq!(reader.read(&mut trailing)?, 0);
+
+        Ok(())
+    }
+
+    /// Tests that two `FileReader`s over the same archive can be read concurrently, interleaved,
+    /// without their reads corrupting each other (they must not rely on a shared fd offset).
+    #[test]
+    fn test_concurrent_file_readers_do_not_interfere() -> anyhow::Result<()> {
+        use std::io::Read;
+
+        let mut archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        let a = archive.get("cl.tga").expect("fixture should contain cl.tga");
+        let b = archive.get("character/skill.sdata").expect("fixture should contain skill.sdata");
+
+        let expected_a = archive.file_data(&a)?;
+        let expected_b = archive.file_data(&b)?;
+
+        let mut reader_a = archive.file_reader(&a)?;
+        let mut reader_b = archive.file_reader(&b)?;
+
+        // Interleave small reads between the two readers.
+        let mut actual_a = Vec::new();
+        let mut actual_b = Vec::new();
+        let mut chunk = [0u8; 7];
+        loop {
+            let read_a = reader_a.read(&mut chunk)?;
+            actual_a.extend_from_slice(&chunk[..read_a]);
+
+            let read_b = reader_b.read(&mut chunk)?;
+            actual_b.extend_from_slice(&chunk[..read_b]);
+
+            if read_a == 0 && read_b == 0 {
+                break;
+            }
+        }
+
+        assert_eq!(expected_a, actual_a);
+        assert_eq!(expected_b, actual_b);
+
+        Ok(())
+    }
+
+    /// Tests that `entries` walks the whole virtual filesystem and reconstructs full paths.
+    #[test]
+    fn test_entries_walks_whole_tree() -> anyhow::Result<()> {
+        let mut archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+
+        let paths: Vec<String> = archive.entries().map(|entry| entry.path).collect();
+        assert!(paths.iter().any(|path| path.eq_ignore_ascii_case("cl.tga")));
+        assert!(paths.iter().any(|path| path.eq_ignore_ascii_case("character/skill.sdata")));
+
+        // The walk should find at least one file, and its path should resolve through a manual lookup.
+        let entries_len = paths.len();
+        assert!(entries_len > 0);
+        assert!(archive.get(&paths[0]).is_some());
+
+        Ok(())
+    }
+
+    /// Tests that `unpack` extracts only the requested subtree, and that the extracted bytes
+    /// match the original entry.
+    #[test]
+    fn test_unpack_extracts_filtered_subtree() -> anyhow::Result<()> {
+        let mut archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        let skill_sdata = archive.get("character/skill.sdata").expect("fixture should contain skill.sdata");
+        let expected = archive.file_data(&skill_sdata)?;
+
+        let dest = Path::new("ep5/unpack_test");
+        if dest.exists() {
+            std::fs::remove_dir_all(dest)?;
+        }
+        std::fs::create_dir_all(dest)?;
+
+        archive.unpack(dest, Some("character"))?;
+
+        let extracted = std::fs::read(dest.join("character/skill.sdata"))?;
+        assert_eq!(expected, extracted);
+        assert!(!dest.join("cl.tga").exists()); // Outside the requested subtree.
+
+        std::fs::remove_dir_all(dest)?;
+        Ok(())
+    }
+
+    /// Tests that `unpack` rejects an entry whose path would escape the destination directory,
+    /// instead of writing outside it (zip-slip/tar-slip).
+    #[test]
+    fn test_unpack_rejects_path_traversal() -> anyhow::Result<()> {
+        let build_dir = Path::new("ep5/traversal_build");
+        if build_dir.exists() {
+            std::fs::remove_dir_all(build_dir)?;
+        }
+        std::fs::create_dir_all(build_dir)?;
+
+        let mut builder = Builder::new(build_dir)?;
+        builder.add_file("../evil.txt", b"should never land on disk")?;
+        let mut archive = builder.finish()?;
+
+        let dest = Path::new("ep5/traversal_dest");
+        if dest.exists() {
+            std::fs::remove_dir_all(dest)?;
+        }
+        std::fs::create_dir_all(dest)?;
+
+        assert!(archive.unpack(dest, None).is_err());
+        assert!(!dest.parent().unwrap().join("evil.txt").exists());
+
+        std::fs::remove_dir_all(build_dir)?;
+        std::fs::remove_dir_all(dest)?;
+        Ok(())
+    }
+
+    /// Tests that `verify_all` passes against the pre-existing checksums in the untouched Ep5
+    /// fixture, without rebuilding it through `Builder` first - `Builder` computes checksums
+    /// with this same native algorithm, so verifying only a self-built archive would be
+    /// circular and wouldn't catch a mismatch against Shaiya's real checksum scheme.
+    #[test]
+    fn test_verify_all_passes_on_untouched_fixture() -> anyhow::Result<()> {
+        let archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        archive.verify_all()?;
+        Ok(())
+    }
+
+    /// Tests that `verify_all` passes on a freshly built archive, and catches corruption of the
+    /// underlying data file.
+    #[test]
+    fn test_verify_all_detects_corruption() -> anyhow::Result<()> {
+        let mut source = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        let file = source.get("cl.tga").expect("fixture should contain cl.tga");
+        let data = source.file_data(&file)?;
+
+        let build_dir = Path::new("ep5/verify_test");
+        if build_dir.exists() {
+            std::fs::remove_dir_all(build_dir)?;
+        }
+        std::fs::create_dir_all(build_dir)?;
+
+        let mut builder = Builder::new(build_dir)?;
+        builder.add_file("cl.tga", &data)?;
+        let built = builder.finish()?;
+        built.verify_all()?;
+
+        // Corrupting the underlying data file should cause verification to fail.
+        let data_path = build_dir.join("data.saf");
+        let mut corrupted = std::fs::read(&data_path)?;
+        corrupted[0] ^= 0xFF;
+        std::fs::write(&data_path, corrupted)?;
+
+        let reopened = Archive::open(&build_dir.join("data.sah"), &data_path)?;
+        assert!(reopened.verify_all().is_err());
+
+        std::fs::remove_dir_all(build_dir)?;
+        Ok(())
+    }
+
+    /// Tests that `decoded_data` passes plain (non-wrapped) files through unchanged.
+    #[cfg(feature = "sdata-layer-unverified")]
+    #[test]
+    fn test_decoded_data_passes_through_non_wrapped_files() -> anyhow::Result<()> {
+        let mut archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        let file = archive.get("cl.tga").expect("fixture should contain cl.tga");
+
+        let raw = archive.file_data(&file)?;
+        let decoded = archive.decoded_data(&file)?;
+        assert_eq!(raw, decoded);
+
+        Ok(())
+    }
+
+    /// Tests the mechanics of `SDataLayer::detect` in isolation - given hand-crafted bytes that
+    /// carry the *candidate* header this crate currently recognises, it strips the header and
+    /// trailer and exposes only the inner payload.
+    ///
+    /// This only proves the stripping logic is internally consistent; it is not evidence that
+    /// `SDATA_MAGIC` or the header layout match Shaiya's real `SData` format, since no genuinely
+    /// wrapped fixture bytes are available to check against - see `SDataLayer`'s docs and
+    /// `test_decoded_data_on_suspected_wrapped_entries` below for the entries that motivated this.
+    #[cfg(feature = "sdata-layer-unverified")]
+    #[test]
+    fn test_sdata_layer_strips_recognised_header_and_trailer() -> anyhow::Result<()> {
+        use crate::archive::SDataLayer;
+        use std::io::{Cursor, Read};
+
+        let mut raw = Vec::new();
+        raw.extend_from_slice(b"SDAT");                 // Candidate magic.
+        raw.extend_from_slice(&1u32.to_le_bytes());     // Candidate version.
+        raw.extend_from_slice(b"hello world");          // Payload.
+        raw.extend_from_slice(&0i32.to_le_bytes());     // Candidate trailing checksum.
+
+        let total_len = raw.len() as u64;
+        let mut layer = SDataLayer::detect(Cursor::new(raw), total_len)?;
+
+        let mut decoded = Vec::new();
+        layer.read_to_end(&mut decoded)?;
+        assert_eq!(decoded, b"hello world");
+
+        Ok(())
+    }
+
+    /// Tests `decoded_data` against the actual fixture entries the request names as motivation
+    /// (`item/item.sdata`, `character/skill.sdata`), not just a non-wrapped file.
+    ///
+    /// Since the real `SData` header layout is unvalidated (see `SDataLayer`'s docs), this can't
+    /// assert specific decoded content - it only pins the invariant that decoding never panics or
+    /// errors, and never yields more bytes than the raw entry.
+    #[cfg(feature = "sdata-layer-unverified")]
+    #[test]
+    fn test_decoded_data_on_suspected_wrapped_entries() -> anyhow::Result<()> {
+        let mut archive = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+
+        for path in ["item/item.sdata", "character/skill.sdata"] {
+            let file = archive.get(path).unwrap_or_else(|| panic!("fixture should contain {path}"));
+            let raw = archive.file_data(&file)?;
+            let decoded = archive.decoded_data(&file)?;
+            assert!(decoded.len() <= raw.len());
+        }
+
+        Ok(())
+    }
+
+    /// Tests that `overlay` adds new entries, replaces existing ones, and supports nested
+    /// folders, while leaving untouched entries alone.
+    #[test]
+    fn test_overlay_applies_patch_add_replace_and_nested() -> anyhow::Result<()> {
+        let base_dir = Path::new("ep5/overlay_base");
+        let patch_dir = Path::new("ep5/overlay_patch");
+        for dir in [base_dir, patch_dir] {
+            if dir.exists() {
+                std::fs::remove_dir_all(dir)?;
+            }
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let mut base_builder = Builder::new(base_dir)?;
+        base_builder.add_file("sysmsg-uni.txt", b"base sysmsg")?;
+        base_builder.add_file("item/item.sdata", b"base item data")?;
+        let mut base = base_builder.finish()?;
+
+        let mut patch_builder = Builder::new(patch_dir)?;
+        patch_builder.add_file("item/item.sdata", b"patched item data")?; // Replace.
+        patch_builder.add_file("character/skill.sdata", b"new skill data")?; // Add, nested folder.
+        let mut patch = patch_builder.finish()?;
+
+        base.overlay(&mut patch)?;
+
+        // The untouched file is still present with its original data.
+        let sysmsg = base.get("sysmsg-uni.txt").expect("untouched file should remain");
+        assert_eq!(base.file_data(&sysmsg)?, b"base sysmsg");
+
+        // The replaced file now has the patch's data.
+        let item = base.get("item/item.sdata").expect("replaced file should remain addressable");
+        assert_eq!(base.file_data(&item)?, b"patched item data");
+
+        // The new, nested file was added.
+        let skill = base.get("character/skill.sdata").expect("new nested file should be added");
+        assert_eq!(base.file_data(&skill)?, b"new skill data");
+
+        std::fs::remove_dir_all(base_dir)?;
+        std::fs::remove_dir_all(patch_dir)?;
+        Ok(())
+    }
+
+    /// Tests that a listing round-trips through JSON and that `from_listing` reconstructs a
+    /// queryable archive from the manifest plus the `.saf` file alone.
+    #[test]
+    fn test_listing_round_trips_through_json_and_from_listing() -> anyhow::Result<()> {
+        let build_dir = Path::new("ep5/listing_test");
+        if build_dir.exists() {
+            std::fs::remove_dir_all(build_dir)?;
+        }
+        std::fs::create_dir_all(build_dir)?;
+
+        let mut builder = Builder::new(build_dir)?;
+        builder.add_file("sysmsg-uni.txt", b"hello listing")?;
+        let mut built = builder.finish()?;
+
+        let listing = built.listing();
+        assert!(listing.entries.iter().any(|entry| entry.path.eq_ignore_ascii_case("sysmsg-uni.txt")));
+
+        let json = listing.to_json()?;
+        let parsed = crate::archive::Listing::from_json(&json)?;
+
+        let data_path = build_dir.join("data.saf");
+        let mut reconstructed = Archive::from_listing(&parsed, &data_path)?;
+
+        let original = built.get("sysmsg-uni.txt").expect("fixture should contain the file");
+        let expected = built.file_data(&original)?;
+
+        let rebuilt_file = reconstructed.get("sysmsg-uni.txt").expect("listing should reconstruct the file");
+        let rebuilt_data = reconstructed.file_data(&rebuilt_file)?;
+        assert_eq!(expected, rebuilt_data);
+
+        std::fs::remove_dir_all(build_dir)?;
+        Ok(())
+    }
+
+    /// Tests that a file round-trips through `Builder` unchanged, using data pulled from the
+    /// known-good Ep5 fixture.
+    #[test]
+    fn test_builder_round_trip() -> anyhow::Result<()> {
+        // Pull a known file's data from the existing fixture.
+        let mut source = Archive::open(Path::new("ep5/data.sah"), Path::new("ep5/data.saf"))?;
+        let original = source.get("cl.tga").expect("fixture should contain cl.tga");
+        let original_data = source.file_data(&original)?;
+
+        // Build a brand new archive containing just that file.
+        let build_dir = Path::new("ep5/build_test");
+        if build_dir.exists() {
+            std::fs::remove_dir_all(build_dir)?;
+        }
+        std::fs::create_dir_all(build_dir)?;
+
+        let mut builder = Builder::new(build_dir)?;
+        builder.add_dir("character");
+        builder.add_file("cl.tga", &original_data)?;
+        let mut built = builder.finish()?;
+
+        // Confirm the rebuilt archive's file contents match the original.
+        let rebuilt_file = built.get("cl.tga").expect("built archive should contain cl.tga");
+        let rebuilt_data = built.file_data(&rebuilt_file)?;
+        assert_eq!(original_data, rebuilt_data);
+        assert!(built.get("character").is_none()); // Empty dirs aren't addressable as files.
+
+        std::fs::remove_dir_all(build_dir)?;
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Error, ErrorKind};
+
+/// A reader bounded to a single file's region within a `.saf` data file.
+///
+/// Reads never cross into neighbouring files, so callers can stream an entry's bytes directly
+/// into a decoder without a full in-memory copy.
+///
+/// Reads are positioned (`pread`/`seek_read`) rather than seek-then-read, so no read depends on
+/// the underlying file description's shared cursor (on Unix it is left untouched; on Windows
+/// `seek_read` moves it as a side effect) - multiple `FileReader`s cloned from the same
+/// `Archive`, or a `FileReader` used alongside a direct `file_data` call, can be read concurrently
+/// without interleaved reads corrupting each other's stream.
+pub struct FileReader {
+    file: File,
+    start: u64,
+    end: u64,
+    position: u64,
+}
+
+impl FileReader {
+
+    /// Creates a new bounded reader over `[offset, offset + length)` of `file`.
+    ///
+    /// # Arguments
+    /// * `file`    - The data file to read from.
+    /// * `offset`  - The offset of the entry within the data file.
+    /// * `length`  - The length of the entry.
+    pub(crate) fn new(file: File, offset: u64, length: u64) -> anyhow::Result<Self> {
+        Ok(Self {
+            file,
+            start: offset,
+            end: offset + length,
+            position: offset,
+        })
+    }
+}
+
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.end.saturating_sub(self.position);
+        if remaining == 0 {
+            return Ok(0);
+        }
+
+        let limit = remaining.min(buf.len() as u64) as usize;
+        let read = read_at(&self.file, &mut buf[..limit], self.position)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for FileReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => self.start.checked_add(offset),
+            SeekFrom::Current(offset) => add_signed(self.position, offset),
+            SeekFrom::End(offset) => add_signed(self.end, offset),
+        }.ok_or_else(|| Error::new(ErrorKind::InvalidInput, "seek position out of bounds"))?;
+
+        // Clamp within the entry's bounds, matching the behaviour of a normal bounded file view.
+        self.position = target.clamp(self.start, self.end);
+        Ok(self.position - self.start)
+    }
+}
+
+/// Reads into `buf` at `offset`, without touching the file's shared cursor.
+///
+/// # Arguments
+/// * `file`    - The file to read from.
+/// * `buf`     - The buffer to read into.
+/// * `offset`  - The absolute offset to read from.
+#[cfg(unix)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    use std::os::unix::fs::FileExt;
+    file.read_at(buf, offset)
+}
+
+/// Reads into `buf` at `offset`, independent of the file's shared cursor (which `seek_read`
+/// moves as a side effect).
+///
+/// # Arguments
+/// * `file`    - The file to read from.
+/// * `buf`     - The buffer to read into.
+/// * `offset`  - The absolute offset to read from.
+#[cfg(windows)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    use std::os::windows::fs::FileExt;
+    file.seek_read(buf, offset)
+}
+
+/// Adds a signed offset to an unsigned position, returning `None` on overflow/underflow.
+fn add_signed(position: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        position.checked_add(offset as u64)
+    } else {
+        position.checked_sub(offset.unsigned_abs())
+    }
+}
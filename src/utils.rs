@@ -1,5 +1,6 @@
-use std::io::Read;
+use std::io::{Read, Write};
 use num::PrimInt;
+use byteorder::{WriteBytesExt, LittleEndian};
 
 pub trait ShaiyaIo {
 
@@ -31,4 +32,37 @@ impl <T> ShaiyaIo for T where T: Read {
         Ok(String::from_utf8_lossy(&data)
             .to_string())
     }
+}
+
+/// The write-side counterpart of [`ShaiyaIo`], used when serialising the virtual filesystem
+/// back out to a SAH header.
+pub trait ShaiyaIoWrite {
+
+    /// Writes a string as a u32 length prefix followed by its raw bytes and a trailing NUL.
+    ///
+    /// The length prefix counts the NUL terminator, matching [`ShaiyaIo::read_fixed_length_string`],
+    /// which strips a trailing NUL from the length it's given - the real on-disk format includes
+    /// the terminator in the recorded length.
+    ///
+    /// # Arguments
+    /// * `value`   - The string to write.
+    fn write_length_prefixed_string(&mut self, value: &str) -> anyhow::Result<()>;
+}
+
+impl <T> ShaiyaIoWrite for T where T: Write {
+
+    /// Writes a string as a u32 length prefix followed by its raw bytes and a trailing NUL.
+    ///
+    /// The length prefix counts the NUL terminator, matching [`ShaiyaIo::read_fixed_length_string`],
+    /// which strips a trailing NUL from the length it's given - the real on-disk format includes
+    /// the terminator in the recorded length.
+    ///
+    /// # Arguments
+    /// * `value`   - The string to write.
+    fn write_length_prefixed_string(&mut self, value: &str) -> anyhow::Result<()> {
+        self.write_u32::<LittleEndian>(value.len() as u32 + 1)?;
+        self.write_all(value.as_bytes())?;
+        self.write_u8(0)?;
+        Ok(())
+    }
 }
\ No newline at end of file